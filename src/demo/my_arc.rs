@@ -1,60 +1,274 @@
+use std::alloc::{self, Layout};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
 use std::ops::Deref;
-use std::ptr::NonNull;
+use std::ptr::{self, addr_of_mut, NonNull};
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+mod raw_alloc;
 
 // Arc 的主要区别在于线程安全的，因此需要使用 AtomicUsize 而不是 Cell<usize> 来管理引用计数。
-struct Arc<T> {
+//
+// T: ?Sized 让 Arc/Weak 也能持有 `[T]`、`dyn Trait` 这类未定长的值，
+// PhantomData<Inner<T>> 告诉编译器这个指针背后逻辑上拥有一个 Inner<T>，
+// 供 drop check 和型变推导使用，本身不占空间。
+struct Arc<T: ?Sized> {
     ptr: NonNull<Inner<T>>,
+    _marker: PhantomData<Inner<T>>,
 }
 
-struct Weak<T> {
+struct Weak<T: ?Sized> {
     ptr: NonNull<Inner<T>>,
+    _marker: PhantomData<Inner<T>>,
 }
 
-struct Inner<T> {
-    value: T,
+// `Inner<T>` 本身只有在 T: Send + Sync 时，跨线程共享/传递它才是安全的
+// —— 这正好是 Arc 能当成"可以扔给另一个线程的引用计数指针"使用的前提，
+// 所以 Send/Sync 按同样的条件转发给 Arc/Weak。
+unsafe impl<T: ?Sized + Send + Sync> Send for Arc<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for Arc<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Send for Weak<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for Weak<T> {}
+
+// 与 std 一致的饱和保护：一旦计数被推过这个阈值就直接中止进程，而不是
+// 让它在 usize 上翻绕回 0 —— 翻绕之后下一次 Drop 就会把本该还活着的对象
+// 提前释放，变成一个隐蔽的 use-after-free。isize::MAX 留了足够大的余量，
+// 在到达它之前，程序早就应该因为别的原因撑不住了。
+const MAX_REFCOUNT: usize = isize::MAX as usize;
+
+fn guard_against_refcount_overflow(old_count: usize) {
+    if old_count > MAX_REFCOUNT {
+        std::process::abort();
+    }
+}
+
+// repr(C) 加上"计数在前、value 在后"：value 作为结构体最后一个字段才能
+// 是未定长类型，这也是 std 里 RcBox 的布局，from_box 手搓的内存布局需要
+// 和编译器生成的保持一致。
+#[repr(C)]
+struct Inner<T: ?Sized> {
     strong_count: AtomicUsize,
     weak_count: AtomicUsize,
+    value: T,
 }
 
 impl<T> Arc<T> {
     fn new(value: T) -> Self {
+        // weak_count 从 1 起步：这一份不对应任何外部 downgrade() 出来的
+        // Weak，而是所有强引用集体持有的一份"隐式份额"，只要还有强引用
+        // 活着它就在，最后一个强引用 drop 时才被释放（见
+        // `Drop for Arc<T>`）。这样自引用节点（new_cyclic）里嵌着的那个
+        // 真正的 Weak 字段才能和这份隐式份额区分开，drop 顺序才不会死锁。
         let inner = Box::new(Inner {
-            value,
             strong_count: AtomicUsize::new(1),
-            weak_count: AtomicUsize::new(0),
+            weak_count: AtomicUsize::new(1),
+            value,
         });
         Arc {
             ptr: unsafe { NonNull::new_unchecked(Box::into_raw(inner)) },
+            _marker: PhantomData,
+        }
+    }
+
+    // 与 Rc::new_cyclic 同样的思路，只是计数换成原子操作：分配时
+    // strong_count 记为 0、weak_count 记为 1，闭包内对拿到的 Weak 调用
+    // upgrade 只会看到 strong_count == 0，于是返回 None —— value 此时还
+    // 没有写入。闭包返回后才把 value 写入并把 strong_count 置 1。
+    fn new_cyclic<F>(data_fn: F) -> Self
+    where
+        F: FnOnce(&Weak<T>) -> T,
+    {
+        let uninit = Box::new(MaybeUninit::<Inner<T>>::uninit());
+        let uninit_ptr: NonNull<MaybeUninit<Inner<T>>> =
+            unsafe { NonNull::new_unchecked(Box::into_raw(uninit)) };
+        let inner_ptr: NonNull<Inner<T>> = uninit_ptr.cast();
+
+        unsafe {
+            let raw = inner_ptr.as_ptr();
+            addr_of_mut!((*raw).strong_count).write(AtomicUsize::new(0));
+            addr_of_mut!((*raw).weak_count).write(AtomicUsize::new(1));
+        }
+
+        let weak = Weak {
+            ptr: inner_ptr,
+            _marker: PhantomData,
+        };
+        let value = data_fn(&weak);
+
+        unsafe {
+            let raw = inner_ptr.as_ptr();
+            addr_of_mut!((*raw).value).write(value);
+            (*raw).strong_count.store(1, Ordering::Release);
+        }
+
+        // 上面这个 weak 已经把分配时记的 weak_count = 1 占上了，这里直接
+        // forget 掉，避免它的 Drop 把计数又减回 0。
+        std::mem::forget(weak);
+
+        Arc {
+            ptr: inner_ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    // 只有在没有别的强引用、也没有活着的弱引用时才把内部值借出去做可变
+    // 访问。用 Acquire 读取两个计数，确保能看到其它线程在各自最后一次
+    // Drop（Release）里对计数的写入，避免漏判"还有人持有"。这里比较的是
+    // 原始的 weak_count（== 1 表示只剩强引用集体持有的那份隐式份额），
+    // 而不是对外隐藏了隐式份额的 `weak_count()`。
+    fn get_mut(&mut self) -> Option<&mut T> {
+        if self.inner().strong_count.load(Ordering::Acquire) == 1
+            && self.inner().weak_count.load(Ordering::Acquire) == 1
+        {
+            Some(unsafe { &mut (*self.ptr.as_ptr()).value })
+        } else {
+            None
+        }
+    }
+
+    // 写时克隆：独占时直接借出内部值；否则新分配一份拷贝，让 self 指向
+    // 它，原来的分配留给其余的强/弱引用，不受这次修改影响。
+    fn make_mut(&mut self) -> &mut T
+    where
+        T: Clone,
+    {
+        let unique = self.inner().strong_count.load(Ordering::Acquire) == 1
+            && self.inner().weak_count.load(Ordering::Acquire) == 1;
+        if !unique {
+            let new_inner = Box::new(Inner {
+                strong_count: AtomicUsize::new(1),
+                weak_count: AtomicUsize::new(1),
+                value: (**self).clone(),
+            });
+            let new_ptr = unsafe { NonNull::new_unchecked(Box::into_raw(new_inner)) };
+
+            // 放弃自己持有的这一份旧强引用，交给 Drop 去处理其余持有者
+            // 或残留 Weak 的收尾。
+            let old = std::mem::replace(
+                self,
+                Arc {
+                    ptr: new_ptr,
+                    _marker: PhantomData,
+                },
+            );
+            drop(old);
         }
+
+        unsafe { &mut (*self.ptr.as_ptr()).value }
     }
+}
 
+impl<T: ?Sized> Arc<T> {
     fn downgrade(&self) -> Weak<T> {
-        self.inner().weak_count.fetch_add(1, Ordering::Relaxed);
-        Weak { ptr: self.ptr }
+        let old_count = self.inner().weak_count.fetch_add(1, Ordering::Relaxed);
+        guard_against_refcount_overflow(old_count);
+        Weak {
+            ptr: self.ptr,
+            _marker: PhantomData,
+        }
     }
 
     fn strong_count(&self) -> usize {
         self.inner().strong_count.load(Ordering::Relaxed)
     }
 
+    // 对外展示的弱引用计数：内部的 weak_count 字段里始终含着强引用们集体
+    // 持有的那一份隐式份额（只要还有强引用活着），这里把它藏起来，只汇报
+    // 真正由 downgrade() 产生的外部 Weak 数量，和 std 的 `Arc::weak_count`
+    // 行为一致。
     fn weak_count(&self) -> usize {
-        self.inner().weak_count.load(Ordering::Relaxed)
+        let raw = self.inner().weak_count.load(Ordering::Relaxed);
+        if self.strong_count() > 0 {
+            raw - 1
+        } else {
+            raw
+        }
     }
 
     fn inner(&self) -> &Inner<T> {
         unsafe { self.ptr.as_ref() }
     }
+
+    // 把一个已经存在的 Box<T>（可能是 `Box<[u8]>`、`Box<dyn Fn()>` 这类
+    // 胖指针）原地"吞并"进一次新分配里：头部放两个原子计数，紧跟着把
+    // value 的字节原样搬过去，最后只释放旧分配的内存（不重复 drop 值）。
+    fn from_box(value: Box<T>) -> Self {
+        unsafe {
+            let value_ptr: *mut T = Box::into_raw(value);
+            let (buf, _value_offset) =
+                raw_alloc::realloc_with_header::<T, (AtomicUsize, AtomicUsize)>(value_ptr);
+
+            (buf as *mut AtomicUsize).write(AtomicUsize::new(1));
+            // weak_count 同样从 1 起步，代表强引用们集体持有的隐式份额，
+            // 和 `Arc::new` 保持一致。
+            (buf.add(std::mem::size_of::<AtomicUsize>()) as *mut AtomicUsize)
+                .write(AtomicUsize::new(1));
+
+            let inner_ptr = raw_alloc::repoint(value_ptr, buf) as *mut Inner<T>;
+
+            Arc {
+                ptr: NonNull::new_unchecked(inner_ptr),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    // 把 Arc 拆成一个指向 value 的裸指针交给调用者，不跑 Drop（计数不变）。
+    // 常用于 FFI：把指针交出去，对方用完后必须通过 `from_raw` 还回来一次，
+    // 也只能还回来一次。
+    fn into_raw(this: Self) -> *const T {
+        let ptr: *const T = unsafe { &(*this.ptr.as_ptr()).value };
+        std::mem::forget(this);
+        ptr
+    }
+
+    // 与 into_raw 配对：从 value 指针反推出 Inner 的起始地址，重新拿回
+    // Arc。`value_offset` 用跟 from_box 同一套 Layout 计算，保证和当初
+    // 分配时的布局一致。安全前提和 std 一样——ptr 必须是上一次
+    // `into_raw` 交出来的、还没被 `from_raw` 消费过的指针。
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        let value_layout = Layout::for_value(&*ptr);
+        let header_layout = Layout::new::<(AtomicUsize, AtomicUsize)>();
+        let (_, value_offset) = header_layout
+            .extend(value_layout)
+            .expect("Arc::from_raw: layout overflow");
+
+        // 不管 T 是不是 ?Sized，指针里的第一个字长都是数据地址，后面
+        // （如果有）才是 metadata，所以只需要在原地把第一个字减去偏移量。
+        let mut raw = ptr as *mut T;
+        let addr_ptr = &mut raw as *mut *mut T as *mut usize;
+        *addr_ptr -= value_offset;
+
+        Arc {
+            ptr: NonNull::new_unchecked(raw as *mut Inner<T>),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Arc<[T]>
+where
+    T: Clone,
+{
+    fn from_slice(slice: &[T]) -> Self {
+        Self::from_box(slice.to_vec().into_boxed_slice())
+    }
 }
 
-impl<T> Clone for Arc<T> {
+impl<T: ?Sized> Clone for Arc<T> {
     fn clone(&self) -> Self {
-        self.inner().strong_count.fetch_add(1, Ordering::Relaxed);
-        Arc { ptr: self.ptr }
+        let old_count = self.inner().strong_count.fetch_add(1, Ordering::Relaxed);
+        guard_against_refcount_overflow(old_count);
+        Arc {
+            ptr: self.ptr,
+            _marker: PhantomData,
+        }
     }
 }
 
-impl<T> Deref for Arc<T> {
+impl<T: ?Sized> Deref for Arc<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -62,38 +276,105 @@ impl<T> Deref for Arc<T> {
     }
 }
 
-impl<T> Drop for Arc<T> {
+impl<T: ?Sized> Drop for Arc<T> {
     fn drop(&mut self) {
-        if self.inner().strong_count.fetch_sub(1, Ordering::Release) == 1 {
+        if self.inner().strong_count.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+        std::sync::atomic::fence(Ordering::Acquire);
+
+        // 最后一个强引用：先记下整块 Inner 的布局（此时 value 还完好），
+        // 再原地跑一次 T 的析构——这会顺带 drop 掉 value 里嵌着的任何
+        // Weak 字段（比如 new_cyclic 构造出的自引用节点里那个指回自己的
+        // Weak），让它们正常地把 weak_count 减下去。析构完成后，再释放
+        // 强引用们集体持有的那一份隐式 weak_count 份额：只有这之后
+        // weak_count 归零，才真正释放内存；如果还有活着的外部 Weak，
+        // Inner 的内存要留到它们也释放完。
+        //
+        // 如果这一步仍然直接判断 weak_count == 0 就整体 `Box::from_raw`
+        // （连 drop 带释放一起做），value 里嵌着的自引用 Weak 永远没有
+        // 机会被 drop，weak_count 也就永远降不到 0——整个分配就死锁式地
+        // 泄漏了，这正是 new_cyclic 构造自引用节点时会触发的情况。
+        let layout = Layout::for_value(self.inner());
+        unsafe {
+            ptr::drop_in_place(&mut (*self.ptr.as_ptr()).value);
+        }
+        if self.inner().weak_count.fetch_sub(1, Ordering::Release) == 1 {
             std::sync::atomic::fence(Ordering::Acquire);
-            if self.weak_count() == 0 {
-                unsafe {
-                    Box::from_raw(self.ptr.as_ptr());
-                } // 释放 Inner
-            } else {
-                self.inner().strong_count.store(0, Ordering::Relaxed);
+            unsafe {
+                alloc::dealloc(self.ptr.as_ptr() as *mut u8, layout);
             }
         }
     }
 }
 
 impl<T> Weak<T> {
+    // 悬空的弱引用：不分配任何 Inner，只是把指针设成一个绝不会是真实
+    // 分配地址的哨兵值（这里用 usize::MAX）。适合放在结构体里先占位，
+    // 等真正的主人出现了再用 downgrade 出来的 Weak 替换掉。
+    fn new() -> Self {
+        Weak {
+            ptr: unsafe { NonNull::new_unchecked(usize::MAX as *mut Inner<T>) },
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Weak<T> {
+    // 是否是 `Weak::new()` 造出来的悬空哨兵。upgrade/strong_count/
+    // weak_count/Drop 都要先查这个，绝不能真的解引用假的 Inner。
+    fn is_dangling(&self) -> bool {
+        self.ptr.as_ptr() as *const () as usize == usize::MAX
+    }
+
     fn upgrade(&self) -> Option<Arc<T>> {
-        let strong_count = self.strong_count();
-        if strong_count == 0 {
-            None
-        } else {
-            self.inner().strong_count.fetch_add(1, Ordering::Relaxed);
-            Some(Arc { ptr: self.ptr })
+        if self.is_dangling() {
+            return None;
         }
+        // 不能先 load 看是不是 0 再单独 fetch_add：这两步之间，最后一个
+        // Arc 可能在另一个线程上已经把 strong_count 降到 0、对 value
+        // 跑完 drop_in_place 甚至释放了整块内存，到时候这里的 fetch_add
+        // 要么在已经析构的内存上把计数“复活”成 1（调用者拿到的 Arc 指向
+        // 一个已经 drop 过的值，之后再 drop 一次就是 double drop），要么
+        // 直接写到已经被释放的内存上。用一次 CAS 循环把“判断非零”和
+        // “加一”合并成一个原子操作，借 0 这个状态做屏障：升级只能在
+        // strong_count 还没归零之前原子地抢到它。
+        self.inner()
+            .strong_count
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |old_count| {
+                if old_count == 0 {
+                    None
+                } else {
+                    guard_against_refcount_overflow(old_count);
+                    Some(old_count + 1)
+                }
+            })
+            .ok()
+            .map(|_| Arc {
+                ptr: self.ptr,
+                _marker: PhantomData,
+            })
     }
 
     fn strong_count(&self) -> usize {
+        if self.is_dangling() {
+            return 0;
+        }
         self.inner().strong_count.load(Ordering::Relaxed)
     }
 
     fn weak_count(&self) -> usize {
-        self.inner().weak_count.load(Ordering::Relaxed)
+        if self.is_dangling() {
+            return 0;
+        }
+        // raw 里含着强引用们集体持有的那一份隐式份额，对外要把它藏起来，
+        // 行为上对齐 std::{rc,sync}::Weak::weak_count。
+        let raw = self.inner().weak_count.load(Ordering::Relaxed);
+        if self.strong_count() > 0 {
+            raw - 1
+        } else {
+            raw
+        }
     }
 
     fn inner(&self) -> &Inner<T> {
@@ -101,26 +382,87 @@ impl<T> Weak<T> {
     }
 }
 
-impl<T> Clone for Weak<T> {
+impl<T: ?Sized> Clone for Weak<T> {
     fn clone(&self) -> Self {
-        self.inner().weak_count.fetch_add(1, Ordering::Relaxed);
-        Weak { ptr: self.ptr }
+        if self.is_dangling() {
+            return Weak {
+                ptr: self.ptr,
+                _marker: PhantomData,
+            };
+        }
+        let old_count = self.inner().weak_count.fetch_add(1, Ordering::Relaxed);
+        guard_against_refcount_overflow(old_count);
+        Weak {
+            ptr: self.ptr,
+            _marker: PhantomData,
+        }
     }
 }
 
-impl<T> Drop for Weak<T> {
+impl<T: ?Sized> Drop for Weak<T> {
     fn drop(&mut self) {
+        if self.is_dangling() {
+            return;
+        }
+        // 计数降到 0 时强引用一定早就已经清空了：weak_count 字段只要还有强
+        // 引用活着就至少是 1（它们集体持有的隐式份额），所以能让这份 Weak
+        // 把计数降到 0 的唯一情况是隐式份额已经在 `Arc::drop` 里被释放
+        // 过——那时候 value 也已经被原地析构过一次了，这里只能单纯释放
+        // 内存，不能再用 `Box::from_raw`（那会对 value 做二次 drop）。
         if self.inner().weak_count.fetch_sub(1, Ordering::Release) == 1 {
             std::sync::atomic::fence(Ordering::Acquire);
-            if self.strong_count() == 0 {
-                unsafe {
-                    Box::from_raw(self.ptr.as_ptr());
-                } // 释放 Inner
+            unsafe {
+                let layout = Layout::for_value(self.inner());
+                alloc::dealloc(self.ptr.as_ptr() as *mut u8, layout);
             }
         }
     }
 }
 
+// 作用域内的循环打破器，RcGuard 的 Arc 版本：用 Mutex 代替 RefCell 来存
+// 登记的回调。回调类型没有 Send 约束，所以 ArcGuard 本身既不是 Send
+// 也不是 Sync，只能在创建它的线程上使用和 drop。
+//
+// 和 RcGuard 一样是 opt-in 的退路，不是自动垃圾回收：只应该在确定被
+// track 的子图不会逃出 guard 的作用域时使用，否则回调可能在数据还被别
+// 处使用时就抢先把链接清空。
+struct ArcGuard {
+    breakers: Mutex<Vec<Box<dyn FnOnce()>>>,
+}
+
+impl ArcGuard {
+    fn new() -> Self {
+        ArcGuard {
+            breakers: Mutex::new(Vec::new()),
+        }
+    }
+
+    // 登记一个 Arc 和"清空它对外强引用"的回调；guard drop 时按登记顺序
+    // 依次调用。`_arc` 本身这里不需要存，只是让调用处表明这个回调是为了
+    // 哪个指针登记的。
+    fn track<T, F>(&self, _arc: &Arc<T>, clear_links: F)
+    where
+        T: ?Sized,
+        F: FnOnce() + 'static,
+    {
+        self.breakers.lock().unwrap().push(Box::new(clear_links));
+    }
+}
+
+impl Drop for ArcGuard {
+    fn drop(&mut self) {
+        // 先把回调整体倒进一个本地 Vec，释放掉 Mutex 的持锁，再逐个调用。
+        // 如果直接 `for breaker in self.breakers.lock().unwrap().drain(..)`，
+        // 锁会在整个循环期间一直被占着——回调里如果又调用了同一个 guard 的
+        // `track`（比如在更大的子图里，某个回调负责把子节点也挂进同一个
+        // guard），就会在同一个线程上对同一把 Mutex 重入加锁，直接死锁。
+        let breakers: Vec<_> = self.breakers.lock().unwrap().drain(..).collect();
+        for breaker in breakers {
+            breaker();
+        }
+    }
+}
+
 fn main() {
     let arc1 = Arc::new(5);
     let weak1 = arc1.downgrade();
@@ -143,4 +485,119 @@ fn main() {
     } else {
         println!("Upgrade failed after drop");
     }
+
+    // 自引用节点：在 new_cyclic 的闭包里就能拿到指向"将来的自己"的 Weak。
+    struct Node {
+        me: Weak<Node>,
+        value: i32,
+    }
+
+    impl Drop for Node {
+        fn drop(&mut self) {
+            println!("Node {} dropped", self.value);
+        }
+    }
+
+    let node = Arc::new_cyclic(|me| Node {
+        me: me.clone(),
+        value: 42,
+    });
+
+    match node.me.upgrade() {
+        Some(upgraded) => println!("new_cyclic upgraded value: {}", upgraded.value),
+        None => println!("new_cyclic upgrade failed"),
+    }
+    drop(node);
+    println!("node dropped, no leak");
+
+    // get_mut / make_mut：独占时可以直接改，多个持有者时触发写时克隆。
+    let mut unique = Arc::new(String::from("hello"));
+    if let Some(s) = unique.get_mut() {
+        s.push_str(", world");
+    }
+    println!("unique after get_mut: {}", *unique);
+
+    let mut shared = Arc::new(vec![1, 2, 3]);
+    let shared_clone = shared.clone();
+    shared.make_mut().push(4);
+    println!("shared after make_mut: {:?}", *shared);
+    println!("shared_clone untouched: {:?}", *shared_clone);
+
+    // Arc<[T]>：一次分配同时承载计数头和切片负载。
+    let arc_slice: Arc<[i32]> = Arc::from_slice(&[10, 20, 30]);
+    println!("arc_slice: {:?}", &*arc_slice);
+
+    // Arc<dyn Fn()>：trait 对象一样能放进同一种 Inner 布局里。
+    let arc_fn: Arc<dyn Fn() -> i32> = Arc::from_box(Box::new(|| 99));
+    println!("arc_fn: {}", arc_fn());
+
+    // Arc<i32>：T 是 Sized（胖指针那一套 metadata 根本不存在），from_box
+    // 同样要能处理——这是 transmute_copy 版本曾经 UB/panic 的那个情形。
+    let arc_sized: Arc<i32> = Arc::from_box(Box::new(42));
+    println!("arc_sized: {}", *arc_sized);
+
+    // ArcGuard：手动斩断一个困在这个作用域里的环。NodeWithDrop 在真正被
+    // 释放时会打印一行，如果环没被斩断这行就永远不会出现。
+    struct NodeWithDrop {
+        name: &'static str,
+        next: RefCell<Option<Arc<NodeWithDrop>>>,
+    }
+
+    impl Drop for NodeWithDrop {
+        fn drop(&mut self) {
+            println!("NodeWithDrop `{}` dropped", self.name);
+        }
+    }
+
+    {
+        let guard = ArcGuard::new();
+
+        let a = Arc::new(NodeWithDrop {
+            name: "a",
+            next: RefCell::new(None),
+        });
+        let b = Arc::new(NodeWithDrop {
+            name: "b",
+            next: RefCell::new(None),
+        });
+        *a.next.borrow_mut() = Some(b.clone());
+        *b.next.borrow_mut() = Some(a.clone()); // 环：a -> b -> a
+
+        guard.track(&a, {
+            let a = a.clone();
+            move || *a.next.borrow_mut() = None
+        });
+        guard.track(&b, {
+            let b = b.clone();
+            move || *b.next.borrow_mut() = None
+        });
+
+        println!("cycle built, dropping guard to break it");
+        drop(guard);
+        println!("guard dropped, a/b about to leave scope");
+    }
+
+    // Send + Sync：真的能把同一个 Arc 发给另一个线程共享。
+    let shared_across_threads = Arc::new(100);
+    let moved = shared_across_threads.clone();
+    let handle = std::thread::spawn(move || *moved);
+    println!("value seen from other thread: {}", handle.join().unwrap());
+
+    // Weak::new()：还没连上任何 Inner 的占位弱引用，upgrade 永远是 None。
+    let empty: Weak<i32> = Weak::new();
+    match empty.upgrade() {
+        Some(_) => println!("dangling weak upgraded (unexpected)"),
+        None => println!("dangling weak upgrade failed, as expected"),
+    }
+
+    // into_raw / from_raw：交出裸指针再原样收回来，中途计数不受影响。
+    let arc_raw = Arc::new(String::from("round-trip"));
+    let raw_ptr = Arc::into_raw(arc_raw);
+    let arc_back = unsafe { Arc::from_raw(raw_ptr) };
+    println!("arc after into_raw/from_raw: {}", *arc_back);
+
+    let arc_slice_raw: Arc<[i32]> = Arc::from_slice(&[1, 2, 3]);
+    let raw_slice_ptr = Arc::into_raw(arc_slice_raw);
+    let arc_slice_back = unsafe { Arc::from_raw(raw_slice_ptr) };
+    println!("arc slice after into_raw/from_raw: {:?}", &*arc_slice_back);
 }
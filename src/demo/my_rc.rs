@@ -1,49 +1,93 @@
+use std::marker::PhantomData;
 use std::ops::Deref;
 use std::ptr::NonNull;
 
+mod raw_alloc;
+
 // 使用 NonNull 来表示非空指针。
 // 实现 Deref trait 以便 Rc 可以像普通引用一样被解引用。比如 *rc
 // Box::into_raw 将一个 Box<T> 转换成一个裸指针。原来的 Box<T>
 // 实例不再负责管理那块内存。稍后使用 Box::from_raw 重新获取所有权，
 // 从而离开作用域才能释放。
 // clone 时增加计数，drop 时减少计数。
-pub(crate) struct MyRc<T> {
+//
+// T: ?Sized 让 MyRc 也能持有 `[T]`、`dyn Trait` 这类未定长的值，
+// PhantomData<Inner<T>> 只是告诉编译器"这个指针背后逻辑上拥有一个
+// Inner<T>"，用于 drop check 和型变推导，本身不占空间。
+pub(crate) struct MyRc<T: ?Sized> {
     ptr: NonNull<Inner<T>>,
+    _marker: PhantomData<Inner<T>>,
 }
 
-struct Inner<T> {
-    value: T,
+// repr(C) 加上"计数在前、value 在后"，是为了让 value 作为结构体最后一个
+// 字段时可以是未定长类型，并且让 from_box 里手搓的内存布局和编译器生成的
+// 布局保持一致，这正是 std 里 RcBox 的做法。
+#[repr(C)]
+struct Inner<T: ?Sized> {
     ref_count: usize,
+    value: T,
 }
 
 impl<T> MyRc<T> {
     pub(crate) fn new(value: T) -> Self {
         let inner = Box::new(Inner {
-            value,
             ref_count: 1,
+            value,
         });
         MyRc {
             ptr: unsafe { NonNull::new_unchecked(Box::into_raw(inner)) },
+            _marker: PhantomData,
         }
     }
+}
 
+impl<T: ?Sized> MyRc<T> {
     pub fn clone(&self) -> Self {
         unsafe {
             (*self.ptr.as_ptr()).ref_count += 1;
         }
-        MyRc { ptr: self.ptr }
+        MyRc {
+            ptr: self.ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    // 把一个已经存在的 Box<T>（可能是 `Box<[u8]>`、`Box<dyn Fn()>` 这类
+    // 胖指针）原地"吞并"进一次新分配里：头部放 ref_count，紧跟着把 value
+    // 的字节原样搬过去，最后只释放旧分配的内存（不重复 drop 值）。
+    pub(crate) fn from_box(value: Box<T>) -> Self {
+        unsafe {
+            let value_ptr: *mut T = Box::into_raw(value);
+            let (buf, _value_offset) = raw_alloc::realloc_with_header::<T, usize>(value_ptr);
+            (buf as *mut usize).write(1);
+            let inner_ptr = raw_alloc::repoint(value_ptr, buf) as *mut Inner<T>;
+
+            MyRc {
+                ptr: NonNull::new_unchecked(inner_ptr),
+                _marker: PhantomData,
+            }
+        }
+    }
+}
+
+impl<T> MyRc<[T]>
+where
+    T: Clone,
+{
+    pub(crate) fn from_slice(slice: &[T]) -> Self {
+        Self::from_box(slice.to_vec().into_boxed_slice())
     }
 }
 
-impl<T> Deref for MyRc<T> {
+impl<T: ?Sized> Deref for MyRc<T> {
     type Target = T;
 
-   fn deref(&self) -> &Self::Target {
+    fn deref(&self) -> &Self::Target {
         unsafe { &(*self.ptr.as_ptr()).value }
     }
 }
 
-impl<T> Drop for MyRc<T> {
+impl<T: ?Sized> Drop for MyRc<T> {
     fn drop(&mut self) {
         unsafe {
             let inner = self.ptr.as_ptr();
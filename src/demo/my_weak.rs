@@ -1,62 +1,262 @@
-use std::cell::Cell;
-use std::ops::Deref;
-use std::ptr::NonNull;
+use std::alloc::{self, Layout};
+use std::cell::{Cell, RefCell, UnsafeCell};
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, addr_of_mut, NonNull};
+
+mod raw_alloc;
 
 // 增加弱引用计数：用于跟踪有多少个弱引用指向相同的对象。
 // Weak 结构体：用于表示弱引用。
 // 管理弱引用的生命周期：在强引用计数和弱引用计数都为零时释放资源
-struct Rc<T> {
+//
+// T: ?Sized 让 Rc/Weak 也能持有 `[T]`、`dyn Trait` 这类未定长的值，
+// PhantomData<Inner<T>> 告诉编译器这个指针背后逻辑上拥有一个 Inner<T>，
+// 供 drop check 和型变推导使用，本身不占空间。
+struct Rc<T: ?Sized> {
     ptr: NonNull<Inner<T>>,
+    _marker: PhantomData<Inner<T>>,
 }
 
-struct Weak<T> {
+struct Weak<T: ?Sized> {
     ptr: NonNull<Inner<T>>,
+    _marker: PhantomData<Inner<T>>,
 }
 
-struct Inner<T> {
-    value: T,
+// 计数用的是 Cell 而不是原子类型，跨线程并发修改会直接产生数据竞争，
+// 所以 Rc/Weak 绝不能被发送或共享到另一个线程。这里没有手写
+// `impl !Send for Rc<T> {}`：负实现目前仍是不稳定特性，不过 `ptr` 这个
+// `NonNull<Inner<T>>` 字段本来就不是 Send/Sync，只要不手动给 Rc/Weak 加
+// `unsafe impl Send/Sync`（本文件也确实没有），编译器就会自动把它们推导
+// 成 !Send/!Sync，效果是一样的——这正是 Arc 那边需要显式 `unsafe impl
+// Send/Sync` 才能跨线程使用的原因。
+
+// repr(C) 加上"计数在前、value 在后"：value 作为结构体最后一个字段才能
+// 是未定长类型，这也是 std 里 RcBox 的布局，from_box 手搓的内存布局需要
+// 和编译器生成的保持一致。
+#[repr(C)]
+struct Inner<T: ?Sized> {
     strong_count: Cell<usize>,
     weak_count: Cell<usize>,
+    value: T,
 }
 
 impl<T> Rc<T> {
     fn new(value: T) -> Self {
+        // weak_count 从 1 起步：这一份不对应任何外部 downgrade() 出来的
+        // Weak，而是所有强引用集体持有的一份"隐式份额"，只要还有强引用
+        // 活着它就在，最后一个强引用 drop 时才被释放（见
+        // `Drop for Rc<T>`）。这样自引用节点（new_cyclic）里嵌着的那个
+        // 真正的 Weak 字段才能和这份隐式份额区分开，drop 顺序才不会死锁。
         let inner = Box::new(Inner {
-            value,
             strong_count: Cell::new(1),
-            weak_count: Cell::new(0),
+            weak_count: Cell::new(1),
+            value,
         });
         Rc {
             ptr: unsafe { NonNull::new_unchecked(Box::into_raw(inner)) },
+            _marker: PhantomData,
         }
     }
 
+    // 构造一个持有回指自身的 `Weak<T>` 的值，用于父子树、双向链表等自引用场景。
+    // 分配时 strong_count 记为 0、weak_count 记为 1（对应传给闭包的这个
+    // `Weak`），所以闭包内部即便对它调用 `upgrade` 也只会拿到 `None` ——
+    // 此时 value 还没有写入，强引用并不存在。闭包返回后才把 value 写进去、
+    // 把 strong_count 置 1，再把 `Rc` 交还给调用者。
+    fn new_cyclic<F>(data_fn: F) -> Self
+    where
+        F: FnOnce(&Weak<T>) -> T,
+    {
+        // 先按 Inner<T> 的大小分配一块未初始化的内存，这样才能在 value
+        // 还不存在的情况下先把计数字段填好、把 Weak 交给闭包。
+        let uninit = Box::new(MaybeUninit::<Inner<T>>::uninit());
+        let uninit_ptr: NonNull<MaybeUninit<Inner<T>>> =
+            unsafe { NonNull::new_unchecked(Box::into_raw(uninit)) };
+        let inner_ptr: NonNull<Inner<T>> = uninit_ptr.cast();
+
+        unsafe {
+            let raw = inner_ptr.as_ptr();
+            addr_of_mut!((*raw).strong_count).write(Cell::new(0));
+            addr_of_mut!((*raw).weak_count).write(Cell::new(1));
+        }
+
+        let weak = Weak {
+            ptr: inner_ptr,
+            _marker: PhantomData,
+        };
+        let value = data_fn(&weak);
+
+        unsafe {
+            let raw = inner_ptr.as_ptr();
+            addr_of_mut!((*raw).value).write(value);
+            (*raw).strong_count.set(1);
+        }
+
+        // 上面这个 weak 已经把分配时记的 weak_count = 1 占上了，这里直接
+        // forget 掉，避免它的 Drop 把计数又减回 0。
+        std::mem::forget(weak);
+
+        Rc {
+            ptr: inner_ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    // 只有在没有别的强引用、也没有活着的弱引用时才把内部值借出去做可变
+    // 访问：这样调用方看到的 &mut T 就不会和别的 Rc/Weak 产生别名。这里
+    // 比较的是原始的 weak_count 字段（== 1 表示只剩强引用集体持有的那份
+    // 隐式份额，没有真正的外部 Weak），而不是对外隐藏了隐式份额的
+    // `weak_count()`。
+    fn get_mut(&mut self) -> Option<&mut T> {
+        if self.strong_count() == 1 && self.inner().weak_count.get() == 1 {
+            Some(unsafe { &mut (*self.ptr.as_ptr()).value })
+        } else {
+            None
+        }
+    }
+
+    // 写时克隆：独占时直接借出内部值；否则新分配一份拷贝，让 self 指向
+    // 它，原来的分配留给其余的强/弱引用，不受这次修改影响。
+    fn make_mut(&mut self) -> &mut T
+    where
+        T: Clone,
+    {
+        if !(self.strong_count() == 1 && self.inner().weak_count.get() == 1) {
+            let new_inner = Box::new(Inner {
+                strong_count: Cell::new(1),
+                weak_count: Cell::new(1),
+                value: (**self).clone(),
+            });
+            let new_ptr = unsafe { NonNull::new_unchecked(Box::into_raw(new_inner)) };
+
+            // 放弃自己持有的这一份旧强引用，交给 Drop 去处理：如果还有
+            // 别的强引用就只是计数减一；如果没有但还有活着的 Weak，旧的
+            // Inner 会留下来直到它们也放手，但 strong_count 归零，永远
+            // upgrade 不回来。
+            let old = std::mem::replace(
+                self,
+                Rc {
+                    ptr: new_ptr,
+                    _marker: PhantomData,
+                },
+            );
+            drop(old);
+        }
+
+        unsafe { &mut (*self.ptr.as_ptr()).value }
+    }
+}
+
+impl<T: ?Sized> Rc<T> {
     fn downgrade(&self) -> Weak<T> {
-        self.inner().weak_count.set(self.weak_count() + 1);
-        Weak { ptr: self.ptr }
+        self.inner()
+            .weak_count
+            .set(self.inner().weak_count.get() + 1);
+        Weak {
+            ptr: self.ptr,
+            _marker: PhantomData,
+        }
     }
 
     fn strong_count(&self) -> usize {
         self.inner().strong_count.get()
     }
 
+    // 对外展示的弱引用计数：内部的 weak_count 字段里始终含着强引用们集体
+    // 持有的那一份隐式份额（只要还有强引用活着），这里把它藏起来，只汇报
+    // 真正由 downgrade() 产生的外部 Weak 数量，和 std 的 `Rc::weak_count`
+    // 行为一致。
     fn weak_count(&self) -> usize {
-        self.inner().weak_count.get()
+        let raw = self.inner().weak_count.get();
+        if self.strong_count() > 0 {
+            raw - 1
+        } else {
+            raw
+        }
     }
 
     fn inner(&self) -> &Inner<T> {
         unsafe { self.ptr.as_ref() }
     }
+
+    // 把一个已经存在的 Box<T>（可能是 `Box<[u8]>`、`Box<dyn Fn()>` 这类
+    // 胖指针）原地"吞并"进一次新分配里：头部放两个计数，紧跟着把 value
+    // 的字节原样搬过去，最后只释放旧分配的内存（不重复 drop 值）。
+    fn from_box(value: Box<T>) -> Self {
+        unsafe {
+            let value_ptr: *mut T = Box::into_raw(value);
+            let (buf, _value_offset) =
+                raw_alloc::realloc_with_header::<T, (Cell<usize>, Cell<usize>)>(value_ptr);
+
+            (buf as *mut Cell<usize>).write(Cell::new(1));
+            // weak_count 同样从 1 起步，代表强引用们集体持有的隐式份额，
+            // 和 `Rc::new` 保持一致。
+            (buf.add(std::mem::size_of::<Cell<usize>>()) as *mut Cell<usize>).write(Cell::new(1));
+
+            let inner_ptr = raw_alloc::repoint(value_ptr, buf) as *mut Inner<T>;
+
+            Rc {
+                ptr: NonNull::new_unchecked(inner_ptr),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    // 把 Rc 拆成一个指向 value 的裸指针交给调用者，不跑 Drop（计数不变）。
+    // 常用于 FFI：把指针交出去，对方用完后必须通过 `from_raw` 还回来一次，
+    // 也只能还回来一次。
+    fn into_raw(this: Self) -> *const T {
+        let ptr: *const T = unsafe { &(*this.ptr.as_ptr()).value };
+        std::mem::forget(this);
+        ptr
+    }
+
+    // 与 into_raw 配对：从 value 指针反推出 Inner 的起始地址，重新拿回
+    // Rc。`value_offset` 用跟 from_box 同一套 Layout 计算，保证和当初
+    // 分配时的布局一致。安全前提和 std 一样——ptr 必须是上一次
+    // `into_raw` 交出来的、还没被 `from_raw` 消费过的指针。
+    unsafe fn from_raw(ptr: *const T) -> Self {
+        let value_layout = Layout::for_value(&*ptr);
+        let header_layout = Layout::new::<(Cell<usize>, Cell<usize>)>();
+        let (_, value_offset) = header_layout
+            .extend(value_layout)
+            .expect("Rc::from_raw: layout overflow");
+
+        // 不管 T 是不是 ?Sized，指针里的第一个字长都是数据地址，后面
+        // （如果有）才是 metadata，所以只需要在原地把第一个字减去偏移量。
+        let mut raw = ptr as *mut T;
+        let addr_ptr = &mut raw as *mut *mut T as *mut usize;
+        *addr_ptr -= value_offset;
+
+        Rc {
+            ptr: NonNull::new_unchecked(raw as *mut Inner<T>),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Rc<[T]>
+where
+    T: Clone,
+{
+    fn from_slice(slice: &[T]) -> Self {
+        Self::from_box(slice.to_vec().into_boxed_slice())
+    }
 }
 
-impl<T> Clone for Rc<T> {
+impl<T: ?Sized> Clone for Rc<T> {
     fn clone(&self) -> Self {
         self.inner().strong_count.set(self.strong_count() + 1);
-        Rc { ptr: self.ptr }
+        Rc {
+            ptr: self.ptr,
+            _marker: PhantomData,
+        }
     }
 }
 
-impl<T> Deref for Rc<T> {
+impl<T: ?Sized> Deref for Rc<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -64,41 +264,98 @@ impl<T> Deref for Rc<T> {
     }
 }
 
-impl<T> Drop for Rc<T> {
+impl<T: ?Sized> Drop for Rc<T> {
     fn drop(&mut self) {
         let strong_count = self.strong_count();
         if strong_count > 1 {
             self.inner().strong_count.set(strong_count - 1);
+            return;
+        }
+
+        // 最后一个强引用：先记下整块 Inner 的布局（此时 value 还完好），
+        // 把 strong_count 清零，再原地跑一次 T 的析构——这会顺带 drop 掉
+        // value 里嵌着的任何 Weak 字段（比如 new_cyclic 构造出的自引用
+        // 节点里的那个指回自己的 Weak），让它们正常地把 weak_count 减
+        // 下去。析构完成后，再释放强引用们集体持有的那一份隐式
+        // weak_count 份额：只有这之后 weak_count 归零，才真正释放内存；
+        // 如果还有活着的外部 Weak，Inner 的内存要留到它们也释放完。
+        //
+        // 如果这一步仍然直接判断 weak_count == 0 就整体 `Box::from_raw`
+        // （连 drop 带释放一起做），value 里嵌着的自引用 Weak 永远没有
+        // 机会被 drop，weak_count 也就永远降不到 0——整个分配就死锁式地
+        // 泄漏了，这正是 new_cyclic 构造自引用节点时会触发的情况。
+        let layout = Layout::for_value(self.inner());
+        self.inner().strong_count.set(0);
+        unsafe {
+            ptr::drop_in_place(&mut (*self.ptr.as_ptr()).value);
+        }
+        let weak_cell = unsafe { &*ptr::addr_of!((*self.ptr.as_ptr()).weak_count) };
+        let weak_count = weak_cell.get();
+        if weak_count > 1 {
+            weak_cell.set(weak_count - 1);
         } else {
-            let weak_count = self.weak_count();
-            if weak_count == 0 {
-                unsafe {
-                    Box::from_raw(self.ptr.as_ptr());
-                } // 释放 Inner
-            } else {
-                self.inner().strong_count.set(0);
+            unsafe {
+                alloc::dealloc(self.ptr.as_ptr() as *mut u8, layout);
             }
         }
     }
 }
 
 impl<T> Weak<T> {
+    // 悬空的弱引用：不分配任何 Inner，只是把指针设成一个绝不会是真实
+    // 分配地址的哨兵值（这里用 usize::MAX）。适合放在结构体里先占位，
+    // 等真正的主人出现了再用 downgrade 出来的 Weak 替换掉。
+    fn new() -> Self {
+        Weak {
+            ptr: unsafe { NonNull::new_unchecked(usize::MAX as *mut Inner<T>) },
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Weak<T> {
+    // 是否是 `Weak::new()` 造出来的悬空哨兵。upgrade/strong_count/
+    // weak_count/Drop 都要先查这个，绝不能真的解引用假的 Inner。
+    fn is_dangling(&self) -> bool {
+        self.ptr.as_ptr() as *const () as usize == usize::MAX
+    }
+
     fn upgrade(&self) -> Option<Rc<T>> {
+        if self.is_dangling() {
+            return None;
+        }
         let strong_count = self.strong_count();
         if strong_count == 0 {
             None
         } else {
             self.inner().strong_count.set(strong_count + 1);
-            Some(Rc { ptr: self.ptr })
+            Some(Rc {
+                ptr: self.ptr,
+                _marker: PhantomData,
+            })
         }
     }
 
     fn strong_count(&self) -> usize {
+        if self.is_dangling() {
+            return 0;
+        }
         self.inner().strong_count.get()
     }
 
+    // 和 `Rc::weak_count` 一样：内部的 weak_count 字段只要强引用还活着就
+    // 始终含着它们集体持有的那一份隐式份额，这里把它藏起来，只汇报真正
+    // 由 downgrade() 产生的外部 Weak 数量。
     fn weak_count(&self) -> usize {
-        self.inner().weak_count.get()
+        if self.is_dangling() {
+            return 0;
+        }
+        let raw = self.inner().weak_count.get();
+        if self.strong_count() > 0 {
+            raw - 1
+        } else {
+            raw
+        }
     }
 
     fn inner(&self) -> &Inner<T> {
@@ -106,31 +363,173 @@ impl<T> Weak<T> {
     }
 }
 
-impl<T> Clone for Weak<T> {
+impl<T: ?Sized> Clone for Weak<T> {
     fn clone(&self) -> Self {
-        self.inner().weak_count.set(self.weak_count() + 1);
-        Weak { ptr: self.ptr }
+        if self.is_dangling() {
+            return Weak {
+                ptr: self.ptr,
+                _marker: PhantomData,
+            };
+        }
+        self.inner()
+            .weak_count
+            .set(self.inner().weak_count.get() + 1);
+        Weak {
+            ptr: self.ptr,
+            _marker: PhantomData,
+        }
     }
 }
 
-impl<T> Drop for Weak<T> {
+impl<T: ?Sized> Drop for Weak<T> {
     fn drop(&mut self) {
-        let weak_count = self.weak_count();
+        if self.is_dangling() {
+            return;
+        }
+        // 计数降到 0 时强引用一定早就已经清空了：weak_count 字段只要还有
+        // 强引用活着就至少是 1（它们集体持有的隐式份额），所以能让这份
+        // Weak 把计数降到 0 的唯一情况是隐式份额已经在 `Rc::drop` 里被
+        // 释放过——那时候 value 也已经被原地析构过一次了，这里只能单纯
+        // 释放内存，不能再跑一次析构（否则就是 double drop）。
+        let weak_count = self.inner().weak_count.get();
         if weak_count > 1 {
             self.inner().weak_count.set(weak_count - 1);
         } else {
-            let strong_count = self.strong_count();
-            if strong_count == 0 {
-                unsafe {
-                    Box::from_raw(self.ptr.as_ptr());
-                } // 释放 Inner
-            } else {
-                self.inner().weak_count.set(0);
+            unsafe {
+                let layout = Layout::for_value(self.inner());
+                alloc::dealloc(self.ptr.as_ptr() as *mut u8, layout);
             }
         }
     }
 }
 
+// 作用域内的循环打破器：纯引用计数拿环状数据结构没办法，两个互相强引用
+// 的 Rc 会永远留在堆上。RcGuard 让你在确定一个子图不会逃出当前作用域的
+// 前提下，手动登记"怎么清空我对外的强链接"，guard 一 drop 就依次执行这些
+// 回调，把环斩断，剩下的引用计数就能照常把内存收回去。
+//
+// 这是 opt-in 的退路，不是自动垃圾回收：如果被 track 的 Rc 逃出了 guard
+// 的作用域（被外部继续持有、或者根本没有在 guard drop 前被其他地方释放
+// 干净），回调仍然会在 guard drop 时执行，可能过早地清掉还在被使用的
+// 数据。只应该在子图确实被整个困在这一个作用域里时使用。
+struct RcGuard {
+    breakers: RefCell<Vec<Box<dyn FnOnce()>>>,
+}
+
+impl RcGuard {
+    fn new() -> Self {
+        RcGuard {
+            breakers: RefCell::new(Vec::new()),
+        }
+    }
+
+    // 登记一个 Rc 和"清空它对外强引用"的回调；guard drop 时按登记顺序
+    // 依次调用。`_rc` 本身这里不需要存，只是让调用处表明这个回调是为了
+    // 哪个指针登记的。
+    fn track<T, F>(&self, _rc: &Rc<T>, clear_links: F)
+    where
+        T: ?Sized,
+        F: FnOnce() + 'static,
+    {
+        self.breakers.borrow_mut().push(Box::new(clear_links));
+    }
+}
+
+impl Drop for RcGuard {
+    fn drop(&mut self) {
+        for breaker in self.breakers.borrow_mut().drain(..) {
+            breaker();
+        }
+    }
+}
+
+// 自制的、带运行时借用检查的内部可变性容器：`state` 用一个有符号计数记
+// 录当前的借用情况——0 表示没人借，正数是共享借用的个数，-1 表示有一个
+// 独占借用。borrow/borrow_mut 在违反别名规则时直接 panic，而不是像
+// `Cell` 那样干脆不让你拿到引用。这一层是 Rc<MyRefCell<T>> 能当作共享
+// 可变图节点使用的关键：Rc 负责"谁还活着"，MyRefCell 负责"同一时刻谁能
+// 改"。
+struct MyRefCell<T> {
+    value: UnsafeCell<T>,
+    state: Cell<isize>,
+}
+
+impl<T> MyRefCell<T> {
+    fn new(value: T) -> Self {
+        MyRefCell {
+            value: UnsafeCell::new(value),
+            state: Cell::new(0),
+        }
+    }
+
+    fn borrow(&self) -> Ref<'_, T> {
+        let state = self.state.get();
+        if state < 0 {
+            panic!("MyRefCell already mutably borrowed");
+        }
+        self.state.set(state + 1);
+        Ref {
+            value: unsafe { &*self.value.get() },
+            state: &self.state,
+        }
+    }
+
+    fn borrow_mut(&self) -> RefMut<'_, T> {
+        if self.state.get() != 0 {
+            panic!("MyRefCell already borrowed");
+        }
+        self.state.set(-1);
+        RefMut {
+            value: unsafe { &mut *self.value.get() },
+            state: &self.state,
+        }
+    }
+}
+
+struct Ref<'b, T> {
+    value: &'b T,
+    state: &'b Cell<isize>,
+}
+
+impl<'b, T> Deref for Ref<'b, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<'b, T> Drop for Ref<'b, T> {
+    fn drop(&mut self) {
+        self.state.set(self.state.get() - 1);
+    }
+}
+
+struct RefMut<'b, T> {
+    value: &'b mut T,
+    state: &'b Cell<isize>,
+}
+
+impl<'b, T> Deref for RefMut<'b, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<'b, T> DerefMut for RefMut<'b, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<'b, T> Drop for RefMut<'b, T> {
+    fn drop(&mut self) {
+        self.state.set(0);
+    }
+}
+
 fn main() {
     let rc1 = Rc::new(5);
     let weak1 = rc1.downgrade();
@@ -153,4 +552,197 @@ fn main() {
     } else {
         println!("Upgrade failed after drop");
     }
+
+    // 自引用节点：在 new_cyclic 的闭包里就能拿到指向"将来的自己"的 Weak。
+    struct Node {
+        me: Weak<Node>,
+        value: i32,
+    }
+
+    // Drop 会打印一行：如果 new_cyclic 构造出的自引用导致 Inner 永远
+    // 释放不掉（weak_count 死锁式地降不到 0），这行就永远不会出现。
+    impl Drop for Node {
+        fn drop(&mut self) {
+            println!("Node {} dropped", self.value);
+        }
+    }
+
+    let node = Rc::new_cyclic(|me| Node {
+        me: me.clone(),
+        value: 42,
+    });
+
+    match node.me.upgrade() {
+        Some(upgraded) => println!("new_cyclic upgraded value: {}", upgraded.value),
+        None => println!("new_cyclic upgrade failed"),
+    }
+    drop(node);
+    println!("node dropped, no leak");
+
+    // get_mut / make_mut：独占时可以直接改，多个持有者时触发写时克隆。
+    let mut unique = Rc::new(String::from("hello"));
+    if let Some(s) = unique.get_mut() {
+        s.push_str(", world");
+    }
+    println!("unique after get_mut: {}", *unique);
+
+    let mut shared = Rc::new(vec![1, 2, 3]);
+    let shared_clone = shared.clone();
+    shared.make_mut().push(4);
+    println!("shared after make_mut: {:?}", *shared);
+    println!("shared_clone untouched: {:?}", *shared_clone);
+
+    // Rc<[T]>：一次分配同时承载计数头和切片负载。
+    let rc_slice: Rc<[i32]> = Rc::from_slice(&[10, 20, 30]);
+    println!("rc_slice: {:?}", &*rc_slice);
+
+    // Rc<dyn Fn()>：trait 对象一样能放进同一种 Inner 布局里。
+    let rc_fn: Rc<dyn Fn() -> i32> = Rc::from_box(Box::new(|| 99));
+    println!("rc_fn: {}", rc_fn());
+
+    // Rc<i32>：T 是 Sized（胖指针那一套 metadata 根本不存在），from_box
+    // 同样要能处理——这是 transmute_copy 版本曾经 UB/panic 的那个情形。
+    let rc_sized: Rc<i32> = Rc::from_box(Box::new(42));
+    println!("rc_sized: {}", *rc_sized);
+
+    // RcGuard：手动斩断一个困在这个作用域里的环。NodeWithDrop 在真正被
+    // 释放时会打印一行，如果环没被斩断这行就永远不会出现。
+    struct NodeWithDrop {
+        name: &'static str,
+        next: RefCell<Option<Rc<NodeWithDrop>>>,
+    }
+
+    impl Drop for NodeWithDrop {
+        fn drop(&mut self) {
+            println!("NodeWithDrop `{}` dropped", self.name);
+        }
+    }
+
+    {
+        let guard = RcGuard::new();
+
+        let a = Rc::new(NodeWithDrop {
+            name: "a",
+            next: RefCell::new(None),
+        });
+        let b = Rc::new(NodeWithDrop {
+            name: "b",
+            next: RefCell::new(None),
+        });
+        *a.next.borrow_mut() = Some(b.clone());
+        *b.next.borrow_mut() = Some(a.clone()); // 环：a -> b -> a
+
+        guard.track(&a, {
+            let a = a.clone();
+            move || *a.next.borrow_mut() = None
+        });
+        guard.track(&b, {
+            let b = b.clone();
+            move || *b.next.borrow_mut() = None
+        });
+
+        println!("cycle built, dropping guard to break it");
+        drop(guard);
+        println!("guard dropped, a/b about to leave scope");
+    }
+
+    // Weak::new()：还没连上任何 Inner 的占位弱引用，upgrade 永远是 None。
+    let empty: Weak<i32> = Weak::new();
+    match empty.upgrade() {
+        Some(_) => println!("dangling weak upgraded (unexpected)"),
+        None => println!("dangling weak upgrade failed, as expected"),
+    }
+
+    // into_raw / from_raw：交出裸指针再原样收回来，中途计数不受影响。
+    let rc_raw = Rc::new(String::from("round-trip"));
+    let raw_ptr = Rc::into_raw(rc_raw);
+    let rc_back = unsafe { Rc::from_raw(raw_ptr) };
+    println!("rc after into_raw/from_raw: {}", *rc_back);
+
+    let rc_slice_raw: Rc<[i32]> = Rc::from_slice(&[1, 2, 3]);
+    let raw_slice_ptr = Rc::into_raw(rc_slice_raw);
+    let rc_slice_back = unsafe { Rc::from_raw(raw_slice_ptr) };
+    println!("rc slice after into_raw/from_raw: {:?}", &*rc_slice_back);
+
+    // MyRefCell 的借用检查：同一时刻只能有一个 borrow_mut，违反就 panic。
+    let cell = MyRefCell::new(10);
+    {
+        let _first = cell.borrow();
+        let prev_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {})); // demo 用，不打印默认的 panic 输出
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| cell.borrow_mut()));
+        std::panic::set_hook(prev_hook);
+        println!("borrow_mut while borrowed panics: {}", result.is_err());
+    }
+    *cell.borrow_mut() += 1;
+    println!("cell after borrow_mut: {}", *cell.borrow());
+
+    // TreeNode：parent 用 Weak 回指，children 用 Rc 强引用，典型的树形结构。
+    struct TreeNode {
+        value: i32,
+        parent: MyRefCell<Weak<TreeNode>>,
+        children: MyRefCell<Vec<Rc<TreeNode>>>,
+    }
+
+    let leaf = Rc::new(TreeNode {
+        value: 3,
+        parent: MyRefCell::new(Weak::new()),
+        children: MyRefCell::new(Vec::new()),
+    });
+
+    match leaf.parent.borrow().upgrade() {
+        Some(p) => println!("leaf parent value: {}", p.value),
+        None => println!("leaf has no parent yet"),
+    }
+
+    let branch = Rc::new(TreeNode {
+        value: 5,
+        parent: MyRefCell::new(Weak::new()),
+        children: MyRefCell::new(vec![leaf.clone()]),
+    });
+    *leaf.parent.borrow_mut() = branch.downgrade();
+
+    match leaf.parent.borrow().upgrade() {
+        Some(p) => println!("leaf parent value after attaching: {}", p.value),
+        None => println!("leaf has no parent yet"),
+    }
+    println!("branch has {} child(ren)", branch.children.borrow().len());
+
+    // 共享可变的 cons list：多个节点可以共享同一条尾巴，借助 MyRefCell
+    // 在需要时把尾巴换成别的 list。
+    enum List {
+        Cons(i32, MyRefCell<Rc<List>>),
+        Nil,
+    }
+
+    impl List {
+        fn head(&self) -> Option<i32> {
+            match self {
+                List::Cons(value, _) => Some(*value),
+                List::Nil => None,
+            }
+        }
+
+        fn tail(&self) -> Option<&MyRefCell<Rc<List>>> {
+            match self {
+                List::Cons(_, tail) => Some(tail),
+                List::Nil => None,
+            }
+        }
+    }
+
+    let a = Rc::new(List::Cons(5, MyRefCell::new(Rc::new(List::Nil))));
+    let b = Rc::new(List::Cons(10, MyRefCell::new(a.clone())));
+    println!("a head: {:?}, b head: {:?}", a.head(), b.head());
+
+    if let Some(tail) = a.tail() {
+        *tail.borrow_mut() = b.clone();
+    }
+    println!("b strong count after a's tail points at it: {}", b.strong_count());
+
+    // 不让这两个节点真的互相强引用到作用域结束：把 a 的尾巴换回 Nil，
+    // 避免和 chunk0-4 的 RcGuard 场景一样造成一个环。
+    if let Some(tail) = a.tail() {
+        *tail.borrow_mut() = Rc::new(List::Nil);
+    }
 }
@@ -0,0 +1,48 @@
+use std::alloc::{self, Layout};
+use std::ptr;
+
+// 被 MyRc/Rc/Arc 的 from_box 共用：把一个已有 Box<T> 的字节原样搬进一次
+// 新分配里，新分配的布局是"头部 `H` 紧跟着 T 自身"，和各自 Inner<T> 的
+// repr(C) 布局保持一致。value 的字节在这里就已经搬到 `value_offset` 处，
+// 调用方只管把自己的计数头写进返回的 `buf` 开头。
+//
+// 返回 `(buf, value_offset)`：`buf` 是新分配的起始地址（头部还没写入，
+// value 字节已经在位），`value_offset` 是 value 字节所在的偏移量。旧的 Box 分配在这
+// 里被释放（零大小值除外，它本来就没有真正分配过）。
+pub(crate) unsafe fn realloc_with_header<T: ?Sized, H>(value_ptr: *mut T) -> (*mut u8, usize) {
+    let value_layout = Layout::for_value(&*value_ptr);
+    let header_layout = Layout::new::<H>();
+    let (combined_layout, value_offset) = header_layout
+        .extend(value_layout)
+        .expect("from_box: layout overflow");
+    let combined_layout = combined_layout.pad_to_align();
+
+    let buf = alloc::alloc(combined_layout);
+    if buf.is_null() {
+        alloc::handle_alloc_error(combined_layout);
+    }
+
+    ptr::copy_nonoverlapping(value_ptr as *const u8, buf.add(value_offset), value_layout.size());
+
+    // 值已经按位搬进新分配了，旧分配只需要释放内存，不能再 drop 一次
+    // 里面的值。零大小的值（比如不捕获变量的闭包）根本没有真正分配过，
+    // Box::into_raw 给的是悬空指针，不能拿去 dealloc。
+    if value_layout.size() != 0 {
+        alloc::dealloc(value_ptr as *mut u8, value_layout);
+    }
+
+    (buf, value_offset)
+}
+
+// 和 into_raw/from_raw 同一套手法：把指向 T 的指针的第一个字（数据地址）
+// 原地换成 `new_addr`，metadata（切片长度/trait 对象 vtable 指针，如果
+// 有的话）保持不动。返回值仍然是 `*mut T`——调用方把它 `as *mut Inner<T>`
+// 就行，因为 Inner<T> 的最后一个字段就是 T 本身，胖指针的 metadata 种类
+// 天然和 T 一致，这个 cast 是稳定 Rust 里允许的。
+// 替代透过固定大小结构体做 `transmute_copy` 的做法——那种做法假设了一个
+// 固定的胖指针宽度，T 是 Sized 时源指针只有一半大小，会直接 UB/panic。
+pub(crate) unsafe fn repoint<T: ?Sized>(mut value_ptr: *mut T, new_addr: *mut u8) -> *mut T {
+    let addr_ptr = &mut value_ptr as *mut *mut T as *mut usize;
+    *addr_ptr = new_addr as usize;
+    value_ptr
+}